@@ -1,11 +1,13 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
-use alloc::{string::String, vec::Vec};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::time::Duration;
 
+use embedded_hal_async::delay::DelayNs;
 use embedded_nal_async::{Dns, TcpConnect};
 use reqwless::{
-    client::HttpClient,
+    client::{HttpClient, TlsConfig},
     headers::ContentType,
     request::{Method, RequestBuilder},
     response,
@@ -19,6 +21,18 @@ pub enum Error {
     /// Attemped authencation and failed.
     Authentication,
 
+    /// Apex REST API reported an application-level failure.
+    ///
+    /// This happens when a mutating call (`feed()`, `set_output()`, ...)
+    /// returns a successful HTTP status but a non-zero `error_code` in its
+    /// JSON body.
+    Api {
+        /// Apex-assigned error code.
+        code: u32,
+        /// Human-readable error message from the Apex.
+        message: String,
+    },
+
     /// Request failed with HTTP error code
     Http(response::StatusCode),
 
@@ -28,6 +42,16 @@ pub enum Error {
     /// JSON (de)serializtion error.
     Json(serde_json::Error),
 
+    /// [`Scheme`] and the `tls` argument passed to [`Apex::new`] disagree:
+    /// [`Scheme::Https`] requires a [`TlsConfig`], [`Scheme::Http`] rejects
+    /// one. Caught here instead of silently building a mismatched
+    /// client/URL pair.
+    SchemeMismatch,
+
+    /// Failed to inflate a gzip-encoded response body.
+    #[cfg(feature = "gzip")]
+    Gzip,
+
     /// Unknown error.
     Unknown,
 }
@@ -60,6 +84,28 @@ struct AuthResponse<'a> {
     pub session_id: &'a str,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct FusionAuthRequest<'a> {
+    pub login: &'a str,
+    pub password: &'a str,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FusionAuthResponse<'a> {
+    pub access_token: &'a str,
+    pub refresh_token: &'a str,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FusionRefreshRequest<'a> {
+    pub refresh_token: &'a str,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FusionRefreshResponse<'a> {
+    pub access_token: &'a str,
+}
+
 /// Neptune Apex system status
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SystemStatus<'a> {
@@ -218,6 +264,39 @@ pub struct InputStatus<'a> {
     pub value: f32,
 }
 
+/// Desired state of an Apex output (outlet, DOS profile, etc).
+///
+/// These map to the `status` values the Apex REST API itself uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputState {
+    /// Force the output off.
+    Off,
+
+    /// Force the output on.
+    On,
+
+    /// Let the output's programmed profile control it.
+    Auto,
+}
+
+impl OutputState {
+    fn as_str(self) -> &'static str {
+        match self {
+            OutputState::Off => "OFF",
+            OutputState::On => "ON",
+            OutputState::Auto => "AUTO",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OutputRequestResponse<'a> {
+    pub did: &'a str,
+    pub status: [&'a str; 1],
+    pub error_code: u32,
+    pub error_message: &'a str,
+}
+
 /// Neptune Apex Status
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Status<'a> {
@@ -266,38 +345,272 @@ struct FeedRequestResponse<'a> {
     pub error_message: &'a str,
 }
 
+/// URL scheme used to talk to the Apex controller.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scheme {
+    /// Plain, unencrypted HTTP.
+    #[default]
+    Http,
+
+    /// TLS-secured HTTPS.
+    ///
+    /// Requires a [`TlsConfig`] to be passed to [`Apex::new`]; see its
+    /// doc comment for how `scheme` and `tls` are kept in sync.
+    Https,
+}
+
+impl Scheme {
+    fn as_str(self) -> &'static str {
+        match self {
+            Scheme::Http => "http://",
+            Scheme::Https => "https://",
+        }
+    }
+}
+
+/// Retry policy controlling how many times [`Apex::request`] will retry a
+/// request after a re-authentication, and how long it waits between
+/// attempts.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts (including the first) before giving up
+    /// with [`Error::Authentication`].
+    ///
+    /// Authenticating for the first time (when there is no cached session
+    /// yet) doesn't consume an attempt, so this only bounds how many times
+    /// the actual request is sent. Treated as `1` if set to `0`.
+    pub max_attempts: usize,
+
+    /// Delay before the first retry. Doubled after each subsequent retry
+    /// (exponential backoff). A zero duration disables the delay.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_millis(0),
+        }
+    }
+}
+
+/// Session credential(s) passed to an [`Apex::on_session_refresh`] callback
+/// whenever fresh credentials are obtained.
+pub enum SessionToken<'a> {
+    /// Local controller session cookie (`connect.sid`).
+    Local(&'a str),
+
+    /// Apex Fusion cloud bearer token pair.
+    ///
+    /// Both fields must be persisted: the access token alone cannot be used
+    /// to mint a new one once it expires.
+    Fusion {
+        /// Bearer token sent as `Authorization: Bearer <access_token>`.
+        access_token: &'a str,
+        /// Token exchanged for a new `access_token` once it expires.
+        refresh_token: &'a str,
+    },
+}
+
+/// Response body bytes that [`Apex::status`] and friends deserialize.
+///
+/// Ordinarily these borrow directly from the caller's `rx_buf`. When the
+/// `gzip` feature is enabled and a response arrives `Content-Encoding:
+/// gzip`, the compressed bytes stay in `rx_buf` but the inflated body needs
+/// its own growable buffer, so that case owns its bytes instead.
+#[cfg(feature = "gzip")]
+pub enum Data<'a> {
+    /// Bytes borrowed directly from the caller's `rx_buf`.
+    Borrowed(&'a [u8]),
+
+    /// Bytes inflated from a gzip-compressed response.
+    Owned(Vec<u8>),
+}
+
+#[cfg(feature = "gzip")]
+impl AsRef<[u8]> for Data<'_> {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Data::Borrowed(data) => data,
+            Data::Owned(data) => data.as_slice(),
+        }
+    }
+}
+
+#[cfg(feature = "gzip")]
+type ResponseData<'a> = Data<'a>;
+#[cfg(not(feature = "gzip"))]
+type ResponseData<'a> = &'a [u8];
+
+/// Inflate a gzip member, skipping its fixed 10-byte header.
+///
+/// The Apex's web server does not send optional gzip header fields (extra
+/// data, filename, comment), so the raw `DEFLATE` stream always starts at
+/// offset 10; the trailing CRC32/size footer is ignored since
+/// `decompress_to_vec` stops once the `DEFLATE` stream ends.
+#[cfg(feature = "gzip")]
+fn inflate_gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let deflate = data.get(10..).ok_or(Error::Gzip)?;
+    miniz_oxide::inflate::decompress_to_vec(deflate).map_err(|_| Error::Gzip)
+}
+
+/// Whether a response's `Content-Encoding` header is `gzip`.
+#[cfg(feature = "gzip")]
+fn is_gzip_encoded<'h>(headers: impl Iterator<Item = (&'h str, &'h str)>) -> bool {
+    headers
+        .filter(|(name, _)| name.eq_ignore_ascii_case("content-encoding"))
+        .any(|(_, value)| value.eq_ignore_ascii_case("gzip"))
+}
+
+/// Base URL of the Apex Fusion cloud service.
+const FUSION_URL_BASE: &str = "https://apexfusion.com/";
+
+/// Authentication backend used by an [`Apex`] client.
+///
+/// `status()`/`feed()`/etc. go through [`Apex::request`] which dispatches on
+/// this enum, so callers can talk to either a local controller or the Apex
+/// Fusion cloud without any other code changes.
+enum AuthScheme {
+    /// Session-cookie auth against a local controller's `rest/login`.
+    Local { session_id: Option<String> },
+
+    /// OAuth2-style bearer-token auth against the Apex Fusion cloud.
+    Fusion {
+        access_token: Option<String>,
+        refresh_token: Option<String>,
+    },
+}
+
 /// Neptune Apex Client
-pub struct Apex<'http, T: TcpConnect + 'http, D: Dns + 'http> {
+pub struct Apex<'http, T: TcpConnect + 'http, D: Dns + 'http, Dl: DelayNs> {
     client: HttpClient<'http, T, D>,
+    delay: Dl,
+    retry_policy: RetryPolicy,
     url_base: String,
     login: String,
     password: String,
-    session_id: Option<String>,
+    auth: AuthScheme,
+    on_session_refresh: Option<Box<dyn FnMut(SessionToken) + 'http>>,
 }
 
-impl<'http, T: TcpConnect + 'http, D: Dns + 'http> Apex<'http, T, D> {
-    /// Create a new Apex client
+impl<'http, T: TcpConnect + 'http, D: Dns + 'http, Dl: DelayNs> Apex<'http, T, D, Dl> {
+    /// Create a new Apex client that talks to a local controller.
+    ///
+    /// `network`/`dns` are used to build the underlying `HttpClient`.
+    /// `session_id` may be optionally passed in if saved from a previous
+    /// session. `delay` is used to sleep between retried requests; see
+    /// [`Apex::with_retry_policy`].
     ///
-    /// `session_id` may be optionally passed in if saved from a previous session.
+    /// `tls` must be `Some` if and only if `scheme` is [`Scheme::Https`] —
+    /// this crate builds the `HttpClient` itself from whichever one is
+    /// given, so `scheme` and the client's actual TLS capability can never
+    /// drift apart the way they could if a pre-built client were accepted.
+    /// Passing a `tls` config with [`Scheme::Http`], or omitting one with
+    /// [`Scheme::Https`], is rejected with [`Error::SchemeMismatch`]. When
+    /// `Https` is used, `tls` must carry a trust anchor for the
+    /// controller's certificate, or have certificate validation disabled
+    /// for self-signed LAN certs.
     pub fn new(
-        client: HttpClient<'http, T, D>,
+        network: &'http T,
+        dns: &'http D,
+        tls: Option<TlsConfig<'http>>,
+        delay: Dl,
+        scheme: Scheme,
         hostname: &str,
         login: &str,
         password: &str,
         session_id: Option<&str>,
     ) -> Result<Self> {
-        let mut url_base = String::from("http://");
+        let client = Self::build_client(network, dns, scheme, tls)?;
+
+        let mut url_base = String::from(scheme.as_str());
         url_base.push_str(hostname);
         url_base.push('/');
         Ok(Self {
             client,
+            delay,
+            retry_policy: RetryPolicy::default(),
             url_base,
             login: String::from(login),
             password: String::from(password),
-            session_id: session_id.map(|s| String::from(s)),
+            auth: AuthScheme::Local {
+                session_id: session_id.map(|s| String::from(s)),
+            },
+            on_session_refresh: None,
         })
     }
 
+    /// Create a new Apex client that talks to the Apex Fusion cloud service.
+    ///
+    /// This lets callers fetch status when they are off the controller's
+    /// LAN. `tokens`, if passed, is an `(access_token, refresh_token)` pair
+    /// saved from a previous session. The Fusion cloud is always reached
+    /// over HTTPS, so `tls` is mandatory (unlike [`Apex::new`]'s optional
+    /// `tls`, which only applies when [`Scheme::Https`] is selected).
+    pub fn new_fusion(
+        network: &'http T,
+        dns: &'http D,
+        tls: TlsConfig<'http>,
+        delay: Dl,
+        login: &str,
+        password: &str,
+        tokens: Option<(&str, &str)>,
+    ) -> Result<Self> {
+        let client = HttpClient::new_with_tls(network, dns, tls);
+        Ok(Self {
+            client,
+            delay,
+            retry_policy: RetryPolicy::default(),
+            url_base: String::from(FUSION_URL_BASE),
+            login: String::from(login),
+            password: String::from(password),
+            auth: AuthScheme::Fusion {
+                access_token: tokens.map(|(access, _)| String::from(access)),
+                refresh_token: tokens.map(|(_, refresh)| String::from(refresh)),
+            },
+            on_session_refresh: None,
+        })
+    }
+
+    /// Build the `HttpClient` for [`Apex::new`], enforcing that `scheme`
+    /// and the presence of `tls` agree.
+    fn build_client(
+        network: &'http T,
+        dns: &'http D,
+        scheme: Scheme,
+        tls: Option<TlsConfig<'http>>,
+    ) -> Result<HttpClient<'http, T, D>> {
+        match (scheme, tls) {
+            (Scheme::Https, Some(tls)) => Ok(HttpClient::new_with_tls(network, dns, tls)),
+            (Scheme::Http, None) => Ok(HttpClient::new(network, dns)),
+            (Scheme::Https, None) | (Scheme::Http, Some(_)) => Err(Error::SchemeMismatch),
+        }
+    }
+
+    /// Override the default [`RetryPolicy`] used by [`Apex::request`].
+    ///
+    /// `retry_policy.max_attempts` is clamped to at least `1`.
+    pub fn with_retry_policy(mut self, mut retry_policy: RetryPolicy) -> Self {
+        retry_policy.max_attempts = retry_policy.max_attempts.max(1);
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Register a callback invoked whenever a fresh [`SessionToken`] (local
+    /// `connect.sid`, or Fusion access/refresh token pair) is obtained, so
+    /// an embedded caller can persist it (e.g. to flash) across reboots.
+    pub fn on_session_refresh(mut self, callback: impl FnMut(SessionToken) + 'http) -> Self {
+        self.on_session_refresh = Some(Box::new(callback));
+        self
+    }
+
+    fn notify_session_refresh(&mut self, token: SessionToken) {
+        if let Some(callback) = &mut self.on_session_refresh {
+            callback(token);
+        }
+    }
+
     fn url(&self, path: &str) -> Result<String> {
         let mut url = String::from(self.url_base.as_str());
         url.push_str(path);
@@ -305,6 +618,13 @@ impl<'http, T: TcpConnect + 'http, D: Dns + 'http> Apex<'http, T, D> {
     }
 
     async fn auth(&mut self, rx_buf: &mut [u8]) -> Result<()> {
+        match &self.auth {
+            AuthScheme::Local { .. } => self.auth_local(rx_buf).await,
+            AuthScheme::Fusion { .. } => self.auth_fusion(rx_buf).await,
+        }
+    }
+
+    async fn auth_local(&mut self, rx_buf: &mut [u8]) -> Result<()> {
         let url = self.url("rest/login")?;
 
         let body = serde_json::to_vec(&AuthRequest {
@@ -314,6 +634,9 @@ impl<'http, T: TcpConnect + 'http, D: Dns + 'http> Apex<'http, T, D> {
         })
         .map_err(|_| ())
         .unwrap();
+        #[cfg(feature = "gzip")]
+        let headers = [("Accept", "*/*"), ("Accept-Encoding", "gzip")];
+        #[cfg(not(feature = "gzip"))]
         let headers = [("Accept", "*/*")];
         let mut requset = self
             .client
@@ -327,63 +650,239 @@ impl<'http, T: TcpConnect + 'http, D: Dns + 'http> Apex<'http, T, D> {
             return Err(Error::Http(response.status));
         }
 
+        #[cfg(feature = "gzip")]
+        let is_gzip = is_gzip_encoded(response.headers());
         let response_data = response.body().read_to_end().await?;
+        #[cfg(feature = "gzip")]
+        let inflated;
+        #[cfg(feature = "gzip")]
+        let response_data = if is_gzip {
+            inflated = inflate_gzip(response_data)?;
+            inflated.as_slice()
+        } else {
+            response_data
+        };
         log::debug!(
             "auth response {:x?}",
             String::from_utf8_lossy(response_data)
         );
-        let auth_response: AuthResponse = serde_json::from_slice(&response_data)?;
+        let auth_response: AuthResponse = serde_json::from_slice(response_data)?;
         log::info!("session id: {}", auth_response.session_id);
 
-        self.session_id = Some(String::from(auth_response.session_id));
+        self.notify_session_refresh(SessionToken::Local(auth_response.session_id));
+        self.auth = AuthScheme::Local {
+            session_id: Some(String::from(auth_response.session_id)),
+        };
+
+        Ok(())
+    }
+
+    async fn auth_fusion(&mut self, rx_buf: &mut [u8]) -> Result<()> {
+        let AuthScheme::Fusion { refresh_token, .. } = &self.auth else {
+            return Err(Error::Unknown);
+        };
+
+        if let Some(refresh_token) = refresh_token.clone() {
+            if self
+                .refresh_fusion_token(rx_buf, &refresh_token)
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
+            log::info!("Refresh token rejected.  Falling back to full login.");
+        }
+
+        let url = self.url("rest/fusion/login")?;
+
+        let body = serde_json::to_vec(&FusionAuthRequest {
+            login: &self.login,
+            password: &self.password,
+        })
+        .map_err(|_| ())
+        .unwrap();
+        #[cfg(feature = "gzip")]
+        let headers = [("Accept", "*/*"), ("Accept-Encoding", "gzip")];
+        #[cfg(not(feature = "gzip"))]
+        let headers = [("Accept", "*/*")];
+        let mut requset = self
+            .client
+            .request(Method::POST, url.as_str())
+            .await?
+            .body(body.as_slice())
+            .content_type(ContentType::ApplicationJson)
+            .headers(&headers);
+        let response = requset.send(rx_buf).await?;
+        if !response.status.is_successful() {
+            return Err(Error::Http(response.status));
+        }
+
+        #[cfg(feature = "gzip")]
+        let is_gzip = is_gzip_encoded(response.headers());
+        let response_data = response.body().read_to_end().await?;
+        #[cfg(feature = "gzip")]
+        let inflated;
+        #[cfg(feature = "gzip")]
+        let response_data = if is_gzip {
+            inflated = inflate_gzip(response_data)?;
+            inflated.as_slice()
+        } else {
+            response_data
+        };
+        let auth_response: FusionAuthResponse = serde_json::from_slice(response_data)?;
+        log::info!("fusion access token obtained");
+
+        self.notify_session_refresh(SessionToken::Fusion {
+            access_token: auth_response.access_token,
+            refresh_token: auth_response.refresh_token,
+        });
+        self.auth = AuthScheme::Fusion {
+            access_token: Some(String::from(auth_response.access_token)),
+            refresh_token: Some(String::from(auth_response.refresh_token)),
+        };
+
+        Ok(())
+    }
+
+    async fn refresh_fusion_token(&mut self, rx_buf: &mut [u8], refresh_token: &str) -> Result<()> {
+        let url = self.url("rest/fusion/refresh")?;
+
+        let body = serde_json::to_vec(&FusionRefreshRequest { refresh_token })
+            .map_err(|_| ())
+            .unwrap();
+        #[cfg(feature = "gzip")]
+        let headers = [("Accept", "*/*"), ("Accept-Encoding", "gzip")];
+        #[cfg(not(feature = "gzip"))]
+        let headers = [("Accept", "*/*")];
+        let mut requset = self
+            .client
+            .request(Method::POST, url.as_str())
+            .await?
+            .body(body.as_slice())
+            .content_type(ContentType::ApplicationJson)
+            .headers(&headers);
+        let response = requset.send(rx_buf).await?;
+        if !response.status.is_successful() {
+            return Err(Error::Http(response.status));
+        }
+
+        #[cfg(feature = "gzip")]
+        let is_gzip = is_gzip_encoded(response.headers());
+        let response_data = response.body().read_to_end().await?;
+        #[cfg(feature = "gzip")]
+        let inflated;
+        #[cfg(feature = "gzip")]
+        let response_data = if is_gzip {
+            inflated = inflate_gzip(response_data)?;
+            inflated.as_slice()
+        } else {
+            response_data
+        };
+        let refresh_response: FusionRefreshResponse = serde_json::from_slice(response_data)?;
+        log::info!("fusion access token refreshed");
+
+        self.notify_session_refresh(SessionToken::Fusion {
+            access_token: refresh_response.access_token,
+            refresh_token,
+        });
+        self.auth = AuthScheme::Fusion {
+            access_token: Some(String::from(refresh_response.access_token)),
+            refresh_token: Some(String::from(refresh_token)),
+        };
 
         Ok(())
     }
 
+    /// Header name/value pair authenticating a request against the current
+    /// backend, or `None` if we have no credentials yet and need to call
+    /// [`Apex::auth`] first.
+    fn auth_header(&self) -> Option<(&'static str, String)> {
+        match &self.auth {
+            AuthScheme::Local {
+                session_id: Some(session_id),
+            } => Some(("Cookie", alloc::format!("connect.sid={session_id}"))),
+            AuthScheme::Fusion {
+                access_token: Some(access_token),
+                ..
+            } => Some(("Authorization", alloc::format!("Bearer {access_token}"))),
+            _ => None,
+        }
+    }
+
     async fn request<'a>(
         &mut self,
         rx_buf: &'a mut [u8],
         method: Method,
         url: &str,
         body: Option<&[u8]>,
-    ) -> Result<&'a [u8]> {
-        // Loop twice to allow authentication attempts.
-        for _ in 0..2 {
-            let Some(session_id) = &self.session_id else {
-                log::info!("No session ID.  Attempting to authenticate.");
-                self.auth(rx_buf).await?;
-                continue;
+    ) -> Result<ResponseData<'a>> {
+        // Authenticating for the first time isn't a caller-visible attempt:
+        // it doesn't touch `url`/`body` at all, so it shouldn't eat into
+        // `retry_policy.max_attempts`.
+        if self.auth_header().is_none() {
+            log::info!("No credentials.  Attempting to authenticate.");
+            self.auth(rx_buf).await?;
+        }
+
+        let mut backoff = self.retry_policy.backoff;
+        let max_attempts = self.retry_policy.max_attempts.max(1);
+        for attempt in 0..max_attempts {
+            let Some((header_name, header_value)) = self.auth_header() else {
+                return Err(Error::Authentication);
             };
-            let cookie = alloc::format!("connect.sid={session_id}");
 
             let url = self.url(url)?;
 
-            let headers = [("Accept", "*/*"), ("Cookie", &cookie)];
+            #[cfg(feature = "gzip")]
+            let headers = [
+                ("Accept", "*/*"),
+                ("Accept-Encoding", "gzip"),
+                (header_name, header_value.as_str()),
+            ];
+            #[cfg(not(feature = "gzip"))]
+            let headers = [("Accept", "*/*"), (header_name, header_value.as_str())];
             let mut request = self
                 .client
                 .request(method, url.as_str())
-                .await
-                .unwrap()
+                .await?
                 .body(body)
                 .headers(&headers);
-            let response = request.send(rx_buf).await.unwrap();
+            let response = request.send(rx_buf).await?;
             let status = response.status;
 
             if status.is_successful() {
+                #[cfg(feature = "gzip")]
+                let is_gzip = is_gzip_encoded(response.headers());
+
                 let response_len = {
-                    let response_data = response.body().read_to_end().await.unwrap();
+                    let response_data = response.body().read_to_end().await?;
                     extern crate std;
                     response_data.len()
                 };
+
+                #[cfg(feature = "gzip")]
+                {
+                    if is_gzip {
+                        return Ok(Data::Owned(inflate_gzip(&rx_buf[..response_len])?));
+                    }
+                    return Ok(Data::Borrowed(&rx_buf[..response_len]));
+                }
+
+                #[cfg(not(feature = "gzip"))]
                 return Ok(&rx_buf[..response_len]);
             }
 
             // Drop request early to drop mutable borrow on self.
             drop(request);
 
-            if status == response::Status::Forbidden {
+            if status == response::Status::Forbidden || status == response::Status::Unauthorized {
                 log::info!("Got authentication failure.  Attempting to re-authenticate.");
                 self.auth(rx_buf).await?;
+
+                if attempt + 1 < max_attempts && !backoff.is_zero() {
+                    self.delay.delay_ms(backoff.as_millis() as u32).await;
+                    backoff *= 2;
+                }
                 continue;
             }
 
@@ -393,7 +892,35 @@ impl<'http, T: TcpConnect + 'http, D: Dns + 'http> Apex<'http, T, D> {
         Err(Error::Authentication)
     }
 
+    /// Fetch status of Apex.
+    ///
+    /// When the `gzip` feature is enabled and the controller's response
+    /// arrives `Content-Encoding: gzip`, the inflated body is written into
+    /// `gzip_buf` instead of `rx_buf` (the compressed bytes occupy
+    /// `rx_buf`, but the inflated ones need their own growable storage);
+    /// pass an empty `Vec` and it will be sized to fit.
+    #[cfg(feature = "gzip")]
+    pub async fn status<'a>(
+        &mut self,
+        rx_buf: &'a mut [u8],
+        gzip_buf: &'a mut Vec<u8>,
+    ) -> Result<Status<'a>> {
+        let data = self
+            .request(rx_buf, Method::GET, "rest/status", None)
+            .await?;
+        let status = match data {
+            Data::Borrowed(data) => serde_json::from_slice(data)?,
+            Data::Owned(owned) => {
+                *gzip_buf = owned;
+                serde_json::from_slice(gzip_buf.as_slice())?
+            }
+        };
+
+        Ok(status)
+    }
+
     /// Fetch status of Apex
+    #[cfg(not(feature = "gzip"))]
     pub async fn status<'a>(&mut self, rx_buf: &'a mut [u8]) -> Result<Status<'a>> {
         let data = self
             .request(rx_buf, Method::GET, "rest/status", None)
@@ -421,7 +948,49 @@ impl<'http, T: TcpConnect + 'http, D: Dns + 'http> Apex<'http, T, D> {
                 Some(body.as_slice()),
             )
             .await?;
-        let _response: FeedRequestResponse = serde_json::from_slice(data)?;
+        let response: FeedRequestResponse = serde_json::from_slice(data.as_ref())?;
+        if response.error_code != 0 {
+            return Err(Error::Api {
+                code: response.error_code,
+                message: String::from(response.error_message),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Set the state of an output (outlet, DOS profile, etc).
+    ///
+    /// `did` is the device ID as reported in [`OutputStatus::did`].
+    pub async fn set_output<'a>(
+        &mut self,
+        rx_buf: &'a mut [u8],
+        did: &str,
+        state: OutputState,
+    ) -> Result<()> {
+        let body = serde_json::to_vec(&OutputRequestResponse {
+            did,
+            status: [state.as_str()],
+            error_code: 0,
+            error_message: "",
+        })
+        .map_err(|_| ())
+        .unwrap();
+        let data = self
+            .request(
+                rx_buf,
+                Method::PUT,
+                &alloc::format!("rest/status/outputs/{did}"),
+                Some(body.as_slice()),
+            )
+            .await?;
+        let response: OutputRequestResponse = serde_json::from_slice(data.as_ref())?;
+        if response.error_code != 0 {
+            return Err(Error::Api {
+                code: response.error_code,
+                message: String::from(response.error_message),
+            });
+        }
 
         Ok(())
     }